@@ -0,0 +1,211 @@
+use std::cmp::Ordering;
+
+use ndarray::{Array, ArrayBase, Data, DataMut, Dimension, NdIndex, OwnedRepr};
+use num_traits::Float;
+
+use crate::rank::{tied_rank, RankMethod};
+
+/// An array paired with a boolean validity mask, analogous to how a sparse
+/// matrix tracks structural nonzeros separately from values. A `true` entry
+/// in the mask means the corresponding data cell is valid; `false` means it
+/// is missing. Unlike encoding missingness as NaN, this works for any
+/// element type, including integers.
+#[derive(Debug)]
+pub struct MaskedArray<A, S, D>
+where
+    S: Data<Elem = A>,
+    D: Dimension,
+{
+    data: ArrayBase<S, D>,
+    valid: Array<bool, D>,
+}
+
+impl<A, S, D> MaskedArray<A, S, D>
+where
+    S: Data<Elem = A>,
+    D: Dimension,
+{
+    /// Builds a masked array from a predicate applied to each element: cells
+    /// for which `predicate` returns `true` are valid.
+    pub fn from_predicate<F>(data: ArrayBase<S, D>, predicate: F) -> Self
+    where
+        F: Fn(&A) -> bool,
+    {
+        let valid = data.map(predicate);
+        MaskedArray { data, valid }
+    }
+
+    /// Returns the underlying data array.
+    pub fn data(&self) -> &ArrayBase<S, D> {
+        &self.data
+    }
+
+    /// Returns the validity mask: `true` where the corresponding data cell
+    /// is valid.
+    pub fn valid(&self) -> &Array<bool, D> {
+        &self.valid
+    }
+
+    /// Returns the number of valid cells.
+    pub fn count_valid(&self) -> usize {
+        self.valid.fold(0, |a, b| a + if *b { 1 } else { 0 })
+    }
+
+    /// Returns the number of invalid (masked-out) cells.
+    pub fn count_invalid(&self) -> usize {
+        self.valid.fold(0, |a, b| a + if *b { 0 } else { 1 })
+    }
+}
+
+impl<A, S, D> MaskedArray<A, S, D>
+where
+    A: Float,
+    S: Data<Elem = A>,
+    D: Dimension,
+{
+    /// Builds a masked array whose validity mask is the data's finiteness:
+    /// a cell is valid iff it is finite (not NaN and not infinite).
+    pub fn from_non_finite(data: ArrayBase<S, D>) -> Self {
+        Self::from_predicate(data, |x| x.is_finite())
+    }
+}
+
+impl<A, S, D> MaskedArray<A, S, D>
+where
+    A: PartialOrd + Copy,
+    S: Data<Elem = A>,
+    D: Dimension,
+    <D as Dimension>::Pattern: NdIndex<D>,
+{
+    /// Returns an array of the same size as the data, where each valid
+    /// value is replaced with a rank among the valid values. Rank zero is
+    /// reserved for masked-out cells, regardless of element type (unlike
+    /// `RankExt::rank`, which can only treat NaN floats this way).
+    pub fn rank(&self, method: RankMethod) -> Array<usize, D> {
+        let mut index_and_value = Vec::new();
+        for (index, element) in self.data.indexed_iter() {
+            if self.valid[index.clone()] {
+                index_and_value.push((index, element));
+            }
+        }
+        index_and_value.sort_unstable_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(Ordering::Equal));
+
+        let mut rank: usize = 1;
+        let mut index: usize = 0;
+        let mut ranks = Array::zeros(self.data.dim());
+        while index < index_and_value.len() {
+            let start_index = index;
+            let current_value = index_and_value.get(index).unwrap().1;
+            while index < index_and_value.len()
+                && index_and_value.get(index).unwrap().1 == current_value
+            {
+                index += 1;
+            }
+
+            let assign_rank = tied_rank(method, rank, index - start_index);
+            for (key, _) in index_and_value[start_index..index].iter() {
+                ranks[key.clone()] = assign_rank;
+            }
+            rank += index - start_index;
+        }
+        ranks
+    }
+
+    /// Returns the elementwise maximum with another masked array. The result
+    /// is valid only where both inputs are valid; where exactly one input is
+    /// invalid, the valid input's value is carried through unchanged so an
+    /// invalid operand never contaminates the result's data with a
+    /// meaningless value (e.g. NaN) that a float-oblivious caller might read
+    /// before checking the mask.
+    pub fn maximum_with<SS>(&self, other: &MaskedArray<A, SS, D>) -> MaskedArray<A, OwnedRepr<A>, D>
+    where
+        SS: Data<Elem = A>,
+    {
+        let mut data = self.data.to_owned();
+        let mut valid = Array::from_elem(self.valid.dim(), false);
+        for (index, val) in data.indexed_iter_mut() {
+            let self_valid = self.valid[index.clone()];
+            let other_valid = other.valid[index.clone()];
+            let o = other.data[index.clone()];
+            *val = match (self_valid, other_valid) {
+                (true, true) if *val < o => o,
+                (true, true) | (true, false) => *val,
+                (false, true) => o,
+                (false, false) => *val,
+            };
+            valid[index.clone()] = self_valid && other_valid;
+        }
+
+        MaskedArray { data, valid }
+    }
+}
+
+impl<A, S, D> MaskedArray<A, S, D>
+where
+    A: Copy,
+    S: DataMut<Elem = A>,
+    D: Dimension,
+    <D as Dimension>::Pattern: NdIndex<D>,
+{
+    /// Fills every masked-out cell with `with` and marks it valid.
+    pub fn fill_masked_inplace(&mut self, with: A) {
+        for (index, val) in self.data.indexed_iter_mut() {
+            if !self.valid[index.clone()] {
+                *val = with;
+            }
+        }
+        self.valid.fill(true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+    use std::f64::NAN;
+
+    #[test]
+    fn from_non_finite_counts() {
+        let masked = MaskedArray::from_non_finite(array![1., NAN, 3., NAN]);
+        assert_eq!(masked.count_valid(), 2);
+        assert_eq!(masked.count_invalid(), 2);
+    }
+
+    #[test]
+    fn from_predicate_counts() {
+        let masked = MaskedArray::from_predicate(array![1, -2, 3, -4], |x| *x > 0);
+        assert_eq!(masked.count_valid(), 2);
+        assert_eq!(masked.count_invalid(), 2);
+    }
+
+    #[test]
+    fn rank_masks_invalid_cells() {
+        let masked = MaskedArray::from_non_finite(array![4., NAN, 1.]);
+        let ranks = masked.rank(RankMethod::Minimum);
+        assert_eq!(ranks, array![2, 0, 1]);
+    }
+
+    #[test]
+    fn rank_masks_integers() {
+        let masked = MaskedArray::from_predicate(array![4, -1, 1], |x| *x > 0);
+        let ranks = masked.rank(RankMethod::Minimum);
+        assert_eq!(ranks, array![2, 0, 1]);
+    }
+
+    #[test]
+    fn maximum_with_propagates_validity() {
+        let lhs = MaskedArray::from_non_finite(array![1., NAN, 3.]);
+        let rhs = MaskedArray::from_non_finite(array![2., 2., NAN]);
+        let result = lhs.maximum_with(&rhs);
+        assert_eq!(result.data(), &array![2., 2., 3.]);
+        assert_eq!(result.valid(), &array![true, false, false]);
+    }
+
+    #[test]
+    fn fill_masked_inplace_marks_valid() {
+        let mut masked = MaskedArray::from_non_finite(array![1., NAN, 3.]);
+        masked.fill_masked_inplace(0.);
+        assert_eq!(masked.data(), &array![1., 0., 3.]);
+        assert_eq!(masked.valid(), &array![true, true, true]);
+    }
+}
@@ -1,4 +1,4 @@
-use ndarray::{Array, ArrayBase, Data, DataMut, Dimension, NdIndex};
+use ndarray::{Array, ArrayBase, Data, DataMut, Dimension, NdIndex, Zip};
 
 pub trait PairwiseInplaceExt<A, S, SS, D>
 where
@@ -16,11 +16,43 @@ pub trait PairwiseExt<A, S, D>
 where
     S: Data<Elem = A>,
 {
-    /// Returns the elementwise maximum with another array.
-    fn maximum_with(&self, other: &ArrayBase<S, D>) -> Array<A, D>;
+    /// Combines this array elementwise with `other` using `f`, broadcasting
+    /// `other` up to this array's shape where it has axes of length 1 (the
+    /// same rule `ndarray`'s arithmetic operators use). Panics if `other`'s
+    /// shape cannot be broadcast to this array's shape.
+    fn combine_with<SS, F>(&self, other: &ArrayBase<SS, D>, f: F) -> Array<A, D>
+    where
+        SS: Data<Elem = A>,
+        F: FnMut(A, A) -> A;
 
-    /// Returns the elementwise minimum with another array.
-    fn minimum_with(&self, other: &ArrayBase<S, D>) -> Array<A, D>;
+    /// Alias for `combine_with`, for callers thinking of this as zipping two
+    /// arrays together elementwise.
+    fn zip_with<SS, F>(&self, other: &ArrayBase<SS, D>, f: F) -> Array<A, D>
+    where
+        SS: Data<Elem = A>,
+        F: FnMut(A, A) -> A,
+    {
+        self.combine_with(other, f)
+    }
+
+    /// Returns the elementwise maximum with another array, broadcasting as
+    /// `combine_with` does.
+    fn maximum_with<SS>(&self, other: &ArrayBase<SS, D>) -> Array<A, D>
+    where
+        SS: Data<Elem = A>;
+
+    /// Returns the elementwise minimum with another array, broadcasting as
+    /// `combine_with` does.
+    fn minimum_with<SS>(&self, other: &ArrayBase<SS, D>) -> Array<A, D>
+    where
+        SS: Data<Elem = A>;
+
+    /// Clamps each element to the elementwise range `[lo, hi]`, broadcasting
+    /// `lo` and `hi` as `combine_with` does.
+    fn clamp_with<SLo, SHi>(&self, lo: &ArrayBase<SLo, D>, hi: &ArrayBase<SHi, D>) -> Array<A, D>
+    where
+        SLo: Data<Elem = A>,
+        SHi: Data<Elem = A>;
 }
 
 impl<A, S, D> PairwiseExt<A, S, D> for ArrayBase<S, D>
@@ -28,18 +60,37 @@ where
     A: PartialOrd + Copy,
     S: Data<Elem = A>,
     D: Dimension,
-    <D as Dimension>::Pattern: NdIndex<D>,
 {
-    fn maximum_with(&self, other: &ArrayBase<S, D>) -> Array<A, D> {
-        let mut array = self.to_owned();
-        array.maximum_with_inplace(other);
-        array
+    fn combine_with<SS, F>(&self, other: &ArrayBase<SS, D>, mut f: F) -> Array<A, D>
+    where
+        SS: Data<Elem = A>,
+        F: FnMut(A, A) -> A,
+    {
+        Zip::from(self)
+            .and_broadcast(other)
+            .map_collect(|a, b| f(*a, *b))
+    }
+
+    fn maximum_with<SS>(&self, other: &ArrayBase<SS, D>) -> Array<A, D>
+    where
+        SS: Data<Elem = A>,
+    {
+        self.combine_with(other, |a, b| if a < b { b } else { a })
+    }
+
+    fn minimum_with<SS>(&self, other: &ArrayBase<SS, D>) -> Array<A, D>
+    where
+        SS: Data<Elem = A>,
+    {
+        self.combine_with(other, |a, b| if a > b { b } else { a })
     }
 
-    fn minimum_with(&self, other: &ArrayBase<S, D>) -> Array<A, D> {
-        let mut array = self.to_owned();
-        array.minimum_with_inplace(other);
-        array
+    fn clamp_with<SLo, SHi>(&self, lo: &ArrayBase<SLo, D>, hi: &ArrayBase<SHi, D>) -> Array<A, D>
+    where
+        SLo: Data<Elem = A>,
+        SHi: Data<Elem = A>,
+    {
+        self.maximum_with(lo).minimum_with(hi)
     }
 }
 
@@ -96,4 +147,26 @@ mod tests {
         lhs.minimum_with_inplace(&rhs);
         assert_eq!(lhs, array![-1, 2, 5]);
     }
+
+    #[test]
+    fn combine_with_custom_fn() {
+        let lhs = array![1, 2, 3];
+        let rhs = array![4, 5, 6];
+        assert_eq!(lhs.combine_with(&rhs, |a, b| a + b), array![5, 7, 9]);
+    }
+
+    #[test]
+    fn maximum_with_broadcasts_row() {
+        let lhs = array![[1., 5.], [9., 2.]];
+        let rhs = array![[4., 1.]];
+        assert_eq!(lhs.maximum_with(&rhs), array![[4., 5.], [9., 2.]]);
+    }
+
+    #[test]
+    fn clamp_with_scalar_bounds() {
+        let lhs = array![-5., 0.5, 5.];
+        let lo = array![0.];
+        let hi = array![1.];
+        assert_eq!(lhs.clamp_with(&lo, &hi), array![0., 0.5, 1.]);
+    }
 }
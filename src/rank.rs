@@ -1,4 +1,6 @@
 use ndarray::{Array, ArrayBase, Axis, Data, Dimension, NdIndex, RemoveAxis};
+use std::cmp::Ordering;
+use std::fmt;
 
 /// Method for breaking ties among ranks. Either the minimum, maximum, or
 /// average rank can be used.
@@ -9,6 +11,161 @@ pub enum RankMethod {
     Average,
 }
 
+/// Controls how elements that cannot be ordered (e.g. NaN in floating point
+/// arrays) are handled by the `try_rank`/`try_discretize` family.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NanPolicy {
+    /// Leave non-orderable elements at rank zero, as `rank`/`discretize` do.
+    Skip,
+    /// Treat non-orderable elements as tied for the lowest rank.
+    Lowest,
+    /// Treat non-orderable elements as tied for the highest rank.
+    Highest,
+    /// Fail with `RankError::NonOrderable` if any element cannot be ordered.
+    Error,
+}
+
+/// Errors produced by the `try_rank`/`try_discretize` family.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RankError {
+    /// An element could not be ordered (e.g. NaN) and `NanPolicy::Error` was
+    /// requested.
+    NonOrderable,
+    /// Two elements that both classified as orderable produced an
+    /// inconsistent comparison, violating a strict weak ordering.
+    InconsistentOrdering,
+}
+
+impl fmt::Display for RankError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RankError::NonOrderable => write!(f, "element could not be ordered"),
+            RankError::InconsistentOrdering => {
+                write!(f, "comparator violated a strict weak ordering")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RankError {}
+
+/// Returns true if the given element is orderable, i.e. it compares equal to
+/// itself. This rejects NaN in floating point types without requiring a
+/// `Float` bound.
+pub(crate) fn is_orderable<A: PartialOrd>(value: &A) -> bool {
+    value.partial_cmp(value).is_some()
+}
+
+/// Returns the rank to assign to a tied group of `count` elements whose
+/// lowest member would be assigned `start_rank` under `RankMethod::Minimum`.
+pub(crate) fn tied_rank(method: RankMethod, start_rank: usize, count: usize) -> usize {
+    match method {
+        RankMethod::Minimum => start_rank,
+        RankMethod::Maximum => start_rank + count - 1,
+        RankMethod::Average => start_rank + (count - 1) / 2,
+    }
+}
+
+/// Assigns bucket identifiers to a rank array in place, reserving bucket zero
+/// for cells that are still at rank zero.
+fn buckets_from_ranks<D>(ranks: &mut Array<usize, D>, buckets: usize)
+where
+    D: Dimension,
+{
+    if let Some(max_rank) = ranks.iter().reduce(|a, b| if *a > *b { a } else { b }) {
+        let max_rank = *max_rank;
+        if max_rank == 0 {
+            return;
+        }
+        let ranks_per_bucket = max_rank / buckets;
+
+        // As a special case, there isn't enough data to cover all the buckets.
+        let (buckets, ranks_per_bucket) = if ranks_per_bucket == 0 {
+            (max_rank, 1)
+        } else {
+            (buckets, ranks_per_bucket)
+        };
+
+        let remainder = max_rank % buckets;
+
+        let mut rank_cut_points = Vec::new();
+        let mut low_rank: usize = 1;
+        // Separate handling of the remainder and non-remainder cases to
+        // allocate the ranks that don't evenly divide the buckets.
+        for _ in 0..remainder {
+            // For example: if the low rank of this bucket is 1 and there
+            // are normally 2 ranks per bucket, then in the remainder case
+            // the first bucket should include the extra element, i.e. the
+            // range [1, 2, 3].
+            let high_rank = low_rank + ranks_per_bucket;
+            rank_cut_points.push(low_rank);
+            low_rank = high_rank + 1;
+        }
+        for _ in remainder..buckets {
+            let high_rank = low_rank + ranks_per_bucket - 1;
+            rank_cut_points.push(low_rank);
+            low_rank = high_rank + 1;
+        }
+        ranks.map_inplace(|x| {
+            if *x == 0 {
+                return;
+            }
+            let mut bucket = 0;
+            for cut in rank_cut_points.iter() {
+                if *x >= *cut {
+                    bucket += 1;
+                } else {
+                    break;
+                }
+            }
+            *x = bucket;
+        });
+    }
+}
+
+/// Converts sorted cut probabilities in (0, 1) into rank cut points given the
+/// maximum assigned rank, merging any probabilities that map to the same
+/// rank so no empty bucket id is skipped.
+fn quantile_cut_points<D>(ranks: &Array<usize, D>, probs: &[f64]) -> Vec<usize>
+where
+    D: Dimension,
+{
+    let max_rank = ranks.iter().reduce(|a, b| if *a > *b { a } else { b }).copied();
+    match max_rank {
+        Some(max_rank) if max_rank > 0 => {
+            let mut cut_points: Vec<usize> = probs
+                .iter()
+                .map(|p| (p * max_rank as f64).ceil() as usize)
+                .collect();
+            cut_points.dedup();
+            cut_points
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Assigns bucket identifiers to a rank array in place from a list of rank
+/// cut points, reserving bucket zero for cells that are still at rank zero.
+fn apply_quantile_cut_points<D>(ranks: &mut Array<usize, D>, cut_points: &[usize])
+where
+    D: Dimension,
+{
+    ranks.map_inplace(|x| {
+        if *x == 0 {
+            return;
+        }
+        let mut met = 0;
+        for cut in cut_points {
+            if *x >= *cut {
+                met += 1;
+            } else {
+                break;
+            }
+        }
+        *x = 1 + met;
+    });
+}
+
 pub trait RankExt<A, S, D>
 where
     S: Data<Elem = A>,
@@ -24,6 +181,30 @@ where
     /// bucket cannot be computed (i.e. NaN values in floating point matrices).
     /// The lowest bucket is one and the maximum bucket is the given number.
     fn discretize(&self, method: RankMethod, buckets: usize) -> Array<usize, D>;
+
+    /// Like `rank`, but returns an error instead of panicking if the
+    /// comparator violates a strict weak ordering, and lets the caller
+    /// control how non-orderable elements (e.g. NaN) are placed via
+    /// `nan_policy`.
+    fn try_rank(&self, method: RankMethod, nan_policy: NanPolicy) -> Result<Array<usize, D>, RankError>;
+
+    /// Like `discretize`, but returns an error instead of panicking, and lets
+    /// the caller control how non-orderable elements are placed via
+    /// `nan_policy`.
+    fn try_discretize(
+        &self,
+        method: RankMethod,
+        buckets: usize,
+        nan_policy: NanPolicy,
+    ) -> Result<Array<usize, D>, RankError>;
+
+    /// Returns an array of the same size as the original, where each value
+    /// is replaced with a bucket identifier, using caller-supplied quantile
+    /// cut points instead of equal-frequency bands. `probs` are sorted cut
+    /// probabilities in (0, 1), e.g. `[0.25, 0.5, 0.75]` to split into
+    /// quartiles. Zero is reserved for elements whose bucket cannot be
+    /// computed, as in `discretize`.
+    fn discretize_quantiles(&self, method: RankMethod, probs: &[f64]) -> Array<usize, D>;
 }
 
 pub trait RankAxisExt<A, S, D>
@@ -41,106 +222,127 @@ where
     /// along the given axis. For example, in a 2d matrix, setting Axis(0) will
     /// bucket elements within rows.
     fn discretize_axis(&self, axis: Axis, method: RankMethod, buckets: usize) -> Array<usize, D>;
+
+    /// Returns an array of the same size as the original, where each value is
+    /// replaced with a quantile bucket across all values sharing that
+    /// element's position along the given axis, using the same caller-supplied
+    /// cut probabilities as `discretize_quantiles`.
+    fn discretize_quantiles_axis(
+        &self,
+        axis: Axis,
+        method: RankMethod,
+        probs: &[f64],
+    ) -> Array<usize, D>;
 }
 
 impl<A, S, D> RankExt<A, S, D> for ArrayBase<S, D>
 where
-    A: PartialOrd + Default,
+    A: PartialOrd,
     S: Data<Elem = A>,
     D: Dimension,
     <D as Dimension>::Pattern: NdIndex<D>,
 {
     fn rank(&self, method: RankMethod) -> Array<usize, D> {
-        let mut index_and_value = Vec::new();
+        self.try_rank(method, NanPolicy::Skip).unwrap()
+    }
+
+    fn discretize(&self, method: RankMethod, buckets: usize) -> Array<usize, D> {
+        self.try_discretize(method, buckets, NanPolicy::Skip)
+            .unwrap()
+    }
+
+    fn try_rank(&self, method: RankMethod, nan_policy: NanPolicy) -> Result<Array<usize, D>, RankError> {
+        let mut orderable = Vec::new();
+        let mut non_orderable = Vec::new();
         for (index, element) in self.indexed_iter() {
-            if element.partial_cmp(&A::default()).is_none() {
-                continue;
+            if is_orderable(element) {
+                orderable.push((index, element));
+            } else {
+                non_orderable.push(index);
             }
-            index_and_value.push((index, element));
         }
-        index_and_value.sort_unstable_by(|a, b| a.1.partial_cmp(b.1).unwrap());
 
-        let mut rank: usize = 1;
+        if !non_orderable.is_empty() && nan_policy == NanPolicy::Error {
+            return Err(RankError::NonOrderable);
+        }
+
+        let mut saw_inconsistent = false;
+        orderable.sort_unstable_by(|a, b| match a.1.partial_cmp(b.1) {
+            Some(ordering) => ordering,
+            None => {
+                saw_inconsistent = true;
+                Ordering::Equal
+            }
+        });
+        if saw_inconsistent {
+            return Err(RankError::InconsistentOrdering);
+        }
+
+        let missing_count = non_orderable.len();
+        let start_rank = match nan_policy {
+            NanPolicy::Lowest => missing_count + 1,
+            _ => 1,
+        };
+
+        let mut rank: usize = start_rank;
         let mut index: usize = 0;
 
         let mut ranks = Array::zeros(self.dim());
-        while index < index_and_value.len() {
+        while index < orderable.len() {
             let start_index = index;
-            let current_value = index_and_value.get(index).unwrap().1;
-            while index < index_and_value.len()
-                && index_and_value.get(index).unwrap().1 == current_value
-            {
+            let current_value = orderable.get(index).unwrap().1;
+            while index < orderable.len() && orderable.get(index).unwrap().1 == current_value {
                 index += 1;
             }
 
-            let assign_rank = match method {
-                RankMethod::Minimum => rank,
-                RankMethod::Maximum => rank + index - start_index - 1,
-                RankMethod::Average => rank + (index - start_index - 1) / 2,
-            };
-            for (key, _) in index_and_value[start_index..index].iter() {
+            let assign_rank = tied_rank(method, rank, index - start_index);
+            for (key, _) in orderable[start_index..index].iter() {
                 ranks[key.clone()] = assign_rank;
             }
             rank += index - start_index;
         }
 
-        return ranks;
-    }
-
-    fn discretize(&self, method: RankMethod, buckets: usize) -> Array<usize, D> {
-        let mut ranks = self.rank(method);
-        if let Some(max_rank) = ranks.iter().reduce(|a, b| if *a > *b { a } else { b }) {
-            let ranks_per_bucket = *max_rank / buckets;
-
-            // As a special case, there isn't enough data to cover all the buckets.
-            let (buckets, ranks_per_bucket) = if ranks_per_bucket == 0 {
-                (*max_rank, 1)
-            } else {
-                (buckets, ranks_per_bucket)
-            };
-
-            let remainder = *max_rank % buckets;
-
-            let mut rank_cut_points = Vec::new();
-            let mut low_rank: usize = 1;
-            // Separate handling of the remainder and non-remainder cases to
-            // allocate the ranks that don't evenly divide the buckets.
-            for _ in 0..remainder {
-                // For example: if the low rank of this bucket is 1 and there
-                // are normally 2 ranks per bucket, then in the remainder case
-                // the first bucket should include the extra element, i.e. the
-                // range [1, 2, 3].
-                let high_rank = low_rank + ranks_per_bucket;
-                rank_cut_points.push(low_rank);
-                low_rank = high_rank + 1;
-            }
-            for _ in remainder..buckets {
-                let high_rank = low_rank + ranks_per_bucket - 1;
-                rank_cut_points.push(low_rank);
-                low_rank = high_rank + 1;
-            }
-            ranks.map_inplace(|x| {
-                if *x == 0 {
-                    return;
+        match nan_policy {
+            NanPolicy::Lowest if missing_count > 0 => {
+                let assign_rank = tied_rank(method, 1, missing_count);
+                for key in non_orderable.iter() {
+                    ranks[key.clone()] = assign_rank;
                 }
-                let mut bucket = 0;
-                for cut in rank_cut_points.iter() {
-                    if *x >= *cut {
-                        bucket += 1;
-                    } else {
-                        break;
-                    }
+            }
+            NanPolicy::Highest if missing_count > 0 => {
+                let assign_rank = tied_rank(method, orderable.len() + 1, missing_count);
+                for key in non_orderable.iter() {
+                    ranks[key.clone()] = assign_rank;
                 }
-                *x = bucket;
-            });
+            }
+            _ => {}
         }
+
+        Ok(ranks)
+    }
+
+    fn try_discretize(
+        &self,
+        method: RankMethod,
+        buckets: usize,
+        nan_policy: NanPolicy,
+    ) -> Result<Array<usize, D>, RankError> {
+        let mut ranks = self.try_rank(method, nan_policy)?;
+        buckets_from_ranks(&mut ranks, buckets);
+        Ok(ranks)
+    }
+
+    fn discretize_quantiles(&self, method: RankMethod, probs: &[f64]) -> Array<usize, D> {
+        let mut ranks = self.rank(method);
+        let cut_points = quantile_cut_points(&ranks, probs);
+        apply_quantile_cut_points(&mut ranks, &cut_points);
         ranks
     }
 }
 
 impl<A, S, D> RankAxisExt<A, S, D> for ArrayBase<S, D>
 where
-    A: PartialOrd + Default,
+    A: PartialOrd,
     S: Data<Elem = A>,
     D: Dimension + RemoveAxis,
     <D as Dimension>::Pattern: NdIndex<D>,
@@ -164,6 +366,20 @@ where
         }
         ranks
     }
+
+    fn discretize_quantiles_axis(
+        &self,
+        axis: Axis,
+        method: RankMethod,
+        probs: &[f64],
+    ) -> Array<usize, D> {
+        let mut ranks = Array::zeros(self.dim());
+        for (i, subarray) in self.axis_iter(axis).enumerate() {
+            let ranked = subarray.discretize_quantiles(method, probs);
+            ranked.assign_to(ranks.index_axis_mut(axis, i));
+        }
+        ranks
+    }
 }
 
 #[cfg(test)]
@@ -255,4 +471,77 @@ mod tests {
         let ranks = arr.discretize(RankMethod::Minimum, 2);
         assert_eq!(ranks, array![[2, 2, 0], [1, 0, 1]]);
     }
+
+    #[test]
+    fn try_rank_error_policy_fails_on_nan() {
+        let arr = array![4., NAN, 1.];
+        assert_eq!(
+            arr.try_rank(RankMethod::Minimum, NanPolicy::Error),
+            Err(RankError::NonOrderable)
+        );
+    }
+
+    #[test]
+    fn try_rank_lowest_policy() {
+        let arr = array![4., NAN, 1.];
+        let ranks = arr.try_rank(RankMethod::Minimum, NanPolicy::Lowest).unwrap();
+        assert_eq!(ranks, array![3, 1, 2]);
+    }
+
+    #[test]
+    fn try_rank_highest_policy() {
+        let arr = array![4., NAN, 1.];
+        let ranks = arr.try_rank(RankMethod::Minimum, NanPolicy::Highest).unwrap();
+        assert_eq!(ranks, array![2, 3, 1]);
+    }
+
+    #[test]
+    fn try_rank_average_lowest_no_missing_values() {
+        let arr = array![4., 3., 2., 1.];
+        let ranks = arr
+            .try_rank(RankMethod::Average, NanPolicy::Lowest)
+            .unwrap();
+        assert_eq!(ranks, array![4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn try_rank_average_highest_no_missing_values() {
+        let arr = array![4., 3., 2., 1.];
+        let ranks = arr
+            .try_rank(RankMethod::Average, NanPolicy::Highest)
+            .unwrap();
+        assert_eq!(ranks, array![4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn discretize_quantiles_quartiles() {
+        let arr = array![1, 2, 3, 4, 5, 6, 7, 8];
+        let buckets = arr.discretize_quantiles(RankMethod::Minimum, &[0.25, 0.5, 0.75]);
+        assert_eq!(buckets, array![1, 2, 2, 3, 3, 4, 4, 4]);
+    }
+
+    #[test]
+    fn discretize_quantiles_merges_duplicate_cuts() {
+        let arr = array![1, 2, 3];
+        // With only 3 ranks, 0.4 and 0.5 both map to the same cut point, so
+        // the middle bucket should not be skipped.
+        let buckets = arr.discretize_quantiles(RankMethod::Minimum, &[0.4, 0.5]);
+        assert_eq!(buckets, array![1, 2, 2]);
+    }
+
+    #[test]
+    fn discretize_quantiles_with_missing_values() {
+        let arr = array![1., 2., NAN, 4.];
+        let buckets = arr.discretize_quantiles(RankMethod::Minimum, &[0.5]);
+        assert_eq!(buckets, array![1, 2, 0, 2]);
+    }
+
+    #[test]
+    fn try_discretize_lowest_policy() {
+        let arr = array![6., 5., NAN, 3., NAN, 1.];
+        let ranks = arr
+            .try_discretize(RankMethod::Minimum, 2, NanPolicy::Lowest)
+            .unwrap();
+        assert_eq!(ranks, array![2, 2, 1, 2, 1, 1]);
+    }
 }
@@ -0,0 +1,260 @@
+use std::cmp::Ordering;
+
+use ndarray::{Array, ArrayBase, Axis, Data, Dimension, Ix1, RemoveAxis};
+
+use crate::rank::{is_orderable, RankMethod};
+
+/// Approximately `2 * log2(len)`, the recursion depth budget past which
+/// `select_nth_in_place` abandons the cheap middle-element pivot in favor of
+/// median-of-medians, guaranteeing worst-case linear time.
+fn depth_budget(len: usize) -> u32 {
+    2 * (usize::BITS - len.max(1).leading_zeros())
+}
+
+/// Sorts at most five values in place, leaving their median at the middle
+/// index; the building block of median-of-medians pivot selection.
+fn median_of_five<A: PartialOrd + Copy>(values: &mut [A]) {
+    values.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+}
+
+/// Deterministically selects a pivot that is guaranteed to leave at least
+/// ~30% of the elements on either side, by recursively taking the median of
+/// medians of groups of five.
+fn median_of_medians<I, A: PartialOrd + Copy>(items: &[(I, &A)]) -> A {
+    const GROUP_SIZE: usize = 5;
+    if items.len() <= GROUP_SIZE {
+        let mut values: Vec<A> = items.iter().map(|(_, v)| **v).collect();
+        median_of_five(&mut values);
+        return values[values.len() / 2];
+    }
+
+    let medians: Vec<A> = items
+        .chunks(GROUP_SIZE)
+        .map(|chunk| {
+            let mut values: Vec<A> = chunk.iter().map(|(_, v)| **v).collect();
+            median_of_five(&mut values);
+            values[values.len() / 2]
+        })
+        .collect();
+    // Select the median of the medians with a zero depth budget, so its
+    // pivots also come from median_of_medians.
+    let mid = medians.len() / 2;
+    let mut median_items: Vec<((), &A)> = medians.iter().map(|v| ((), v)).collect();
+    select_nth_in_place(&mut median_items, mid, 0);
+    *median_items[mid].1
+}
+
+/// Partitions `items` in place around a pivot into `< pivot`, `== pivot`, and
+/// `> pivot` regions (Dutch national flag partitioning), then recurses only
+/// into whichever region contains position `n`. Falls back to
+/// `median_of_medians` for pivot selection once `depth_budget` is exhausted,
+/// which bounds worst-case recursion depth and guarantees linear time.
+fn select_nth_in_place<I, A>(items: &mut [(I, &A)], n: usize, depth_budget: u32)
+where
+    A: PartialOrd + Copy,
+{
+    let len = items.len();
+    if len <= 1 {
+        return;
+    }
+
+    let pivot = if depth_budget == 0 {
+        median_of_medians(items)
+    } else {
+        *items[len / 2].1
+    };
+
+    let mut lt = 0;
+    let mut gt = len;
+    let mut i = 0;
+    while i < gt {
+        match items[i].1.partial_cmp(&pivot) {
+            Some(Ordering::Less) => {
+                items.swap(i, lt);
+                lt += 1;
+                i += 1;
+            }
+            Some(Ordering::Greater) => {
+                gt -= 1;
+                items.swap(i, gt);
+            }
+            _ => i += 1,
+        }
+    }
+
+    if n < lt {
+        select_nth_in_place(&mut items[..lt], n, depth_budget.saturating_sub(1));
+    } else if n >= gt {
+        select_nth_in_place(&mut items[gt..], n - gt, depth_budget.saturating_sub(1));
+    }
+    // Otherwise n falls in the `== pivot` bucket, which is already in place.
+}
+
+pub trait SelectExt<A, S, D>
+where
+    S: Data<Elem = A>,
+    D: Dimension,
+{
+    /// Returns the `n`-th order statistic (zero-indexed, ascending by value)
+    /// together with its flat index (in the same order as `.iter()`),
+    /// without fully sorting the array. Elements that cannot be ordered
+    /// (e.g. NaN) are skipped, as in `RankExt::rank`, so `n` indexes among
+    /// the orderable elements only. Ties are broken by `method`: `Minimum`
+    /// returns the lowest index among equal elements, `Maximum` the highest,
+    /// and `Average` behaves like `Minimum`.
+    ///
+    /// Panics if `n` is not less than the number of orderable elements.
+    fn select_nth(&self, n: usize, method: RankMethod) -> (usize, &A);
+
+    /// Returns the indices of the `k` largest elements, in descending order
+    /// of value, without fully sorting the array. Elements that cannot be
+    /// ordered (e.g. NaN) are skipped, as in `RankExt::rank`. If `k` exceeds
+    /// the number of orderable elements, every orderable index is returned.
+    fn top_k(&self, k: usize) -> Array<D::Pattern, Ix1>;
+}
+
+pub trait SelectAxisExt<A, S, D>
+where
+    S: Data<Elem = A>,
+    D: Dimension + RemoveAxis,
+{
+    /// Returns, for each lane sharing a position along `axis` (the same
+    /// grouping `RankAxisExt::rank_axis` uses), the indices of the `k`
+    /// largest elements in that lane, in descending order of value. Lanes
+    /// with fewer than `k` orderable elements yield a shorter result.
+    fn top_k_axis(&self, axis: Axis, k: usize) -> Vec<Array<<D::Smaller as Dimension>::Pattern, Ix1>>;
+}
+
+impl<A, S, D> SelectExt<A, S, D> for ArrayBase<S, D>
+where
+    A: PartialOrd + Copy,
+    S: Data<Elem = A>,
+    D: Dimension,
+{
+    fn select_nth(&self, n: usize, method: RankMethod) -> (usize, &A) {
+        let mut items: Vec<(usize, &A)> = self
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| is_orderable(value))
+            .collect();
+        assert!(
+            n < items.len(),
+            "n must be less than the number of orderable elements"
+        );
+
+        let budget = depth_budget(items.len());
+        select_nth_in_place(&mut items, n, budget);
+
+        let value = *items[n].1;
+        let mut low = n;
+        while low > 0 && *items[low - 1].1 == value {
+            low -= 1;
+        }
+        let mut high = n + 1;
+        while high < items.len() && *items[high].1 == value {
+            high += 1;
+        }
+
+        let tied = &items[low..high];
+        *match method {
+            RankMethod::Minimum | RankMethod::Average => {
+                tied.iter().min_by_key(|(index, _)| *index).unwrap()
+            }
+            RankMethod::Maximum => tied.iter().max_by_key(|(index, _)| *index).unwrap(),
+        }
+    }
+
+    fn top_k(&self, k: usize) -> Array<D::Pattern, Ix1> {
+        let mut items: Vec<(D::Pattern, &A)> = self
+            .indexed_iter()
+            .filter(|(_, value)| is_orderable(value))
+            .collect();
+        let k = k.min(items.len());
+        if k == 0 {
+            return Array::from_vec(Vec::new());
+        }
+
+        let cut = items.len() - k;
+        let budget = depth_budget(items.len());
+        select_nth_in_place(&mut items, cut, budget);
+
+        let mut top = items.split_off(cut);
+        top.sort_unstable_by(|a, b| b.1.partial_cmp(a.1).unwrap());
+        Array::from_vec(top.into_iter().map(|(index, _)| index).collect())
+    }
+}
+
+impl<A, S, D> SelectAxisExt<A, S, D> for ArrayBase<S, D>
+where
+    A: PartialOrd + Copy,
+    S: Data<Elem = A>,
+    D: Dimension + RemoveAxis,
+{
+    fn top_k_axis(&self, axis: Axis, k: usize) -> Vec<Array<<D::Smaller as Dimension>::Pattern, Ix1>> {
+        self.axis_iter(axis)
+            .map(|subarray| subarray.top_k(k))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+    use std::f64::NAN;
+
+    #[test]
+    fn select_nth_no_ties() {
+        let arr = array![5, 3, 1, 4, 2];
+        assert_eq!(arr.select_nth(0, RankMethod::Minimum), (2, &1));
+        assert_eq!(arr.select_nth(4, RankMethod::Minimum), (0, &5));
+        assert_eq!(arr.select_nth(2, RankMethod::Minimum), (1, &3));
+    }
+
+    #[test]
+    fn select_nth_skips_nan() {
+        let arr = array![5., NAN, 1., 4., 2.];
+        assert_eq!(arr.select_nth(0, RankMethod::Minimum), (2, &1.));
+        assert_eq!(arr.select_nth(3, RankMethod::Minimum), (0, &5.));
+    }
+
+    #[test]
+    fn select_nth_ties() {
+        let arr = array![1, 3, 1, 2, 1];
+        assert_eq!(arr.select_nth(0, RankMethod::Minimum), (0, &1));
+        assert_eq!(arr.select_nth(0, RankMethod::Maximum), (4, &1));
+    }
+
+    #[test]
+    fn top_k_basic() {
+        let arr = array![5, 3, 1, 4, 2];
+        let mut top = arr.top_k(2).to_vec();
+        top.sort_unstable();
+        assert_eq!(top, vec![0, 3]);
+    }
+
+    #[test]
+    fn top_k_descending_order() {
+        let arr = array![5, 3, 1, 4, 2];
+        assert_eq!(arr.top_k(3), array![0, 3, 1]);
+    }
+
+    #[test]
+    fn top_k_skips_nan() {
+        let arr = array![5., NAN, 1., 4., 2.];
+        assert_eq!(arr.top_k(2), array![0, 3]);
+    }
+
+    #[test]
+    fn top_k_exceeds_len() {
+        let arr = array![2, 1];
+        assert_eq!(arr.top_k(5), array![0, 1]);
+    }
+
+    #[test]
+    fn top_k_axis_rows() {
+        let arr = array![[6, 1, 5], [2, 4, 3]];
+        let top = arr.top_k_axis(Axis(0), 2);
+        assert_eq!(top, vec![array![0, 2], array![1, 2]]);
+    }
+}
@@ -0,0 +1,154 @@
+use ndarray::{s, Array, ArrayBase, Axis, Data, Dimension};
+use num_traits::Float;
+
+use crate::rank::{RankExt, RankMethod};
+
+/// Describes the half-open range `[lower, lower + length)` of a single
+/// rolling window along one dimension, so out-of-range windows can be
+/// rejected cleanly rather than panicking on indexing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DimRange {
+    pub lower: usize,
+    pub length: usize,
+}
+
+impl DimRange {
+    pub fn new(lower: usize, length: usize) -> Self {
+        DimRange { lower, length }
+    }
+
+    /// The exclusive upper bound of this range.
+    pub fn upper(&self) -> usize {
+        self.lower + self.length
+    }
+
+    /// Returns true if this range fits entirely within `[0, len)`.
+    pub fn contained_by(&self, len: usize) -> bool {
+        self.upper() <= len
+    }
+}
+
+/// Returns the window bounds (as `DimRange`s of the given `window` length)
+/// that fit within a lane of length `len`, stepping by `stride`.
+fn window_ranges(len: usize, window: usize, stride: usize) -> Vec<DimRange> {
+    let mut ranges = Vec::new();
+    if window == 0 || stride == 0 {
+        return ranges;
+    }
+    let mut lower = 0;
+    loop {
+        let range = DimRange::new(lower, window);
+        if !range.contained_by(len) {
+            break;
+        }
+        ranges.push(range);
+        lower += stride;
+    }
+    ranges
+}
+
+pub trait WindowExt<A, S, D>
+where
+    A: Float,
+    S: Data<Elem = A>,
+    D: Dimension,
+{
+    /// Slides a window of `window` elements along `axis`, stepping by
+    /// `stride`, and counts the finite values in each window position. The
+    /// returned array has the same shape as the original, except its length
+    /// along `axis` is `(len - window) / stride + 1`.
+    fn count_finite_window(&self, axis: Axis, window: usize, stride: usize) -> Array<usize, D>;
+
+    /// Slides a window of `window` elements along `axis` (stepping by one
+    /// position at a time) and, for each window, returns the rank of its
+    /// last element among the window's values as `RankExt::rank` computes
+    /// it (NaN is left unranked, infinities are ranked) -- a rolling rank of
+    /// the most recent observation. The returned array has the same shape
+    /// as the original, except its length along `axis` is `len - window + 1`.
+    fn rank_window(&self, axis: Axis, window: usize, method: RankMethod) -> Array<usize, D>;
+}
+
+impl<A, S, D> WindowExt<A, S, D> for ArrayBase<S, D>
+where
+    A: Float,
+    S: Data<Elem = A>,
+    D: Dimension,
+{
+    fn count_finite_window(&self, axis: Axis, window: usize, stride: usize) -> Array<usize, D> {
+        let ranges = window_ranges(self.len_of(axis), window, stride);
+
+        let mut out_dim = self.raw_dim();
+        out_dim[axis.index()] = ranges.len();
+        let mut out = Array::zeros(out_dim);
+
+        for (lane, mut out_lane) in self.lanes(axis).into_iter().zip(out.lanes_mut(axis)) {
+            for (slot, range) in out_lane.iter_mut().zip(ranges.iter()) {
+                let window_view = lane.slice(s![range.lower..range.upper()]);
+                *slot = window_view.fold(0, |a, b| a + if b.is_finite() { 1 } else { 0 });
+            }
+        }
+
+        out
+    }
+
+    fn rank_window(&self, axis: Axis, window: usize, method: RankMethod) -> Array<usize, D> {
+        let ranges = window_ranges(self.len_of(axis), window, 1);
+
+        let mut out_dim = self.raw_dim();
+        out_dim[axis.index()] = ranges.len();
+        let mut out = Array::zeros(out_dim);
+
+        for (lane, mut out_lane) in self.lanes(axis).into_iter().zip(out.lanes_mut(axis)) {
+            for (slot, range) in out_lane.iter_mut().zip(ranges.iter()) {
+                let window_view = lane.slice(s![range.lower..range.upper()]);
+                let ranks = window_view.rank(method);
+                *slot = ranks[ranks.len() - 1];
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+    use std::f64::NAN;
+
+    #[test]
+    fn dim_range_bounds() {
+        let range = DimRange::new(2, 3);
+        assert_eq!(range.upper(), 5);
+        assert!(range.contained_by(5));
+        assert!(!range.contained_by(4));
+    }
+
+    #[test]
+    fn count_finite_window_vector() {
+        let arr = array![1., 2., NAN, 4., 5.];
+        let counts = arr.count_finite_window(Axis(0), 2, 1);
+        assert_eq!(counts, array![2, 1, 1, 2]);
+    }
+
+    #[test]
+    fn count_finite_window_stride() {
+        let arr = array![1., 2., NAN, 4., 5., 6.];
+        let counts = arr.count_finite_window(Axis(0), 2, 2);
+        assert_eq!(counts, array![2, 1, 2]);
+    }
+
+    #[test]
+    fn count_finite_window_too_short() {
+        let arr = array![1., 2.];
+        let counts = arr.count_finite_window(Axis(0), 3, 1);
+        assert_eq!(counts, Array::<usize, _>::zeros(0));
+    }
+
+    #[test]
+    fn rank_window_vector() {
+        let arr = array![3., 1., 2., 4.];
+        let ranks = arr.rank_window(Axis(0), 2, RankMethod::Minimum);
+        assert_eq!(ranks, array![1, 2, 2]);
+    }
+}